@@ -0,0 +1,89 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Self = Self::rgb(0x00, 0x00, 0x00);
+    pub const WHITE: Self = Self::rgb(0xFF, 0xFF, 0xFF);
+    pub const GRAY: Self = Self::rgb(0x80, 0x80, 0x80);
+    pub const YELLOW: Self = Self::rgb(0xFF, 0xFF, 0x00);
+    pub const RED: Self = Self::rgb(0xFF, 0x00, 0x00);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, 0xFF)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid color {:?}, expected e.g. \"#RRGGBB[AA]\"", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    // Parses "#RRGGBB" or "#RRGGBBAA" (alpha defaults to opaque).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+        let (r, g, b) = match (byte(0), byte(2), byte(4)) {
+            (Some(r), Some(g), Some(b)) => (r, g, b),
+            _ => return Err(ParseColorError(s.to_string())),
+        };
+        let a = match hex.len() {
+            6 => 0xFF,
+            8 => byte(6).ok_or_else(|| ParseColorError(s.to_string()))?,
+            _ => return Err(ParseColorError(s.to_string())),
+        };
+
+        Ok(Self::rgba(r, g, b, a))
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rgb_and_rgba() {
+        assert_eq!("#FF0000".parse(), Ok(Color::rgb(0xFF, 0x00, 0x00)));
+        assert_eq!("112233".parse(), Ok(Color::rgb(0x11, 0x22, 0x33)));
+        assert_eq!("#11223344".parse(), Ok(Color::rgba(0x11, 0x22, 0x33, 0x44)));
+    }
+
+    #[test]
+    fn rejects_malformed_hex() {
+        assert!("#nope".parse::<Color>().is_err());
+        assert!("#1234".parse::<Color>().is_err());
+    }
+}
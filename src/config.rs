@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::color::Color;
+
+const DEFAULT_CONFIG_PATH: &str = "/etc/ddlm.toml";
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub theme: Theme,
+    pub sessions: Sessions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            sessions: Sessions::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Falls back to defaults if the file is missing or malformed.
+    pub fn load() -> Self {
+        Self::load_from(DEFAULT_CONFIG_PATH)
+    }
+
+    fn load_from(path: &str) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|data| toml::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub background: Color,
+    pub box_color: Color,
+    pub text: Color,
+    pub highlight: Color,
+    pub error: Color,
+
+    pub box_width: u32,
+    pub box_height: u32,
+
+    pub headline_font: FontConfig,
+    pub prompt_font: FontConfig,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: Color::BLACK,
+            box_color: Color::GRAY,
+            text: Color::WHITE,
+            highlight: Color::YELLOW,
+            error: Color::RED,
+            box_width: 1024,
+            box_height: 168,
+            headline_font: FontConfig::default(),
+            prompt_font: FontConfig::default(),
+        }
+    }
+}
+
+// `size` is optional so a partial override (e.g. just `path`) doesn't clobber
+// the caller's own default size with some other field's default. See
+// `load_font`, which picks the effective size.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct FontConfig {
+    pub path: Option<PathBuf>,
+    pub size: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Sessions {
+    pub directories: Vec<PathBuf>,
+}
+
+impl Default for Sessions {
+    fn default() -> Self {
+        Self {
+            directories: vec![
+                PathBuf::from("/usr/share/wayland-sessions"),
+                PathBuf::from("/usr/share/xsessions"),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_font_override_keeps_its_own_default_size() {
+        let theme: Theme = toml::from_str(
+            r#"
+            [headline_font]
+            path = "/tmp/font.ttf"
+            "#,
+        )
+        .unwrap();
+
+        // The override only touched `path`; `size` must stay unset here, not
+        // silently pick up FontConfig's own (nonexistent) struct-level default.
+        assert_eq!(theme.headline_font.size, None);
+        assert_eq!(theme.prompt_font.size, Theme::default().prompt_font.size);
+    }
+
+    #[test]
+    fn missing_file_falls_back_to_defaults() {
+        let config = Config::load_from("/nonexistent/ddlm.toml");
+        assert_eq!(config.theme.box_width, Theme::default().box_width);
+    }
+}
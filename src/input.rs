@@ -1,7 +1,14 @@
+use std::collections::HashMap;
 use std::io::{Bytes, Read, StdinLock};
+use std::os::unix::io::AsRawFd;
+use std::sync::OnceLock;
 
 use framebuffer::{Framebuffer, KdMode};
 
+// How long to wait for a follow-up byte before treating a lone 0x1B as the
+// Escape key rather than the start of a sequence. Same idea as vim's ttimeoutlen.
+const ESCAPE_TIMEOUT_MS: i32 = 25;
+
 pub struct InputStream {
     inner: Bytes<StdinLock<'static>>,
 }
@@ -27,40 +34,223 @@ impl InputStream {
             0x04 => Key::CtrlD,
             0x0B => Key::CtrlK,
             0x15 => Key::CtrlU,
+            0x17 => Key::CtrlW,
             0x7F => Key::Backspace,
             b'\t' => Key::Tab,
             b'\r' => Key::Return,
-            0x1B => match self.next_byte() {
-                b'[' => match self.next_byte() {
-                    b'A' => Key::Up,
-                    b'B' => Key::Down,
-                    b'C' => Key::Right,
-                    b'D' => Key::Left,
-                    b => Key::OtherCsi(b),
-                },
-                b => Key::OtherEsc(b),
-            },
-            b => Key::Other(b),
+            0x1B => self.decode_escape(),
+            b if b >= 0x80 => Key::Char(self.decode_utf8(b)),
+            b => Key::Char(b as char),
+        }
+    }
+
+    // Walks the escape-sequence trie; a dead end falls back to OtherEsc/OtherCsi.
+    // A lone 0x1B with nothing following within ESCAPE_TIMEOUT_MS is the Escape key.
+    fn decode_escape(&mut self) -> Key {
+        if !byte_pending(ESCAPE_TIMEOUT_MS) {
+            return Key::Escape;
+        }
+
+        let mut node = escape_trie();
+        let mut depth = 0;
+        let mut in_csi = false;
+
+        loop {
+            let b = self.next_byte();
+            if depth == 0 {
+                in_csi = b == b'[';
+            }
+            depth += 1;
+
+            match node.children.get(&b) {
+                Some(child) if child.children.is_empty() => return child.key.unwrap(),
+                Some(child) => node = child,
+                None if in_csi => return Key::OtherCsi(b),
+                None => return Key::OtherEsc(b),
+            }
+        }
+    }
+
+    fn decode_utf8(&mut self, first: u8) -> char {
+        let len = utf8_seq_len(first).unwrap_or(1);
+
+        let mut buf = [0u8; 4];
+        buf[0] = first;
+        for slot in &mut buf[1..len] {
+            let b = self.next_byte();
+            if b & 0xC0 != 0x80 {
+                return char::REPLACEMENT_CHARACTER;
+            }
+            *slot = b;
         }
+
+        decode_utf8_bytes(&buf[..len])
+    }
+}
+
+// 0xC0-0xDF -> 2 bytes, 0xE0-0xEF -> 3, 0xF0-0xF7 -> 4, else not a valid lead byte.
+fn utf8_seq_len(first: u8) -> Option<usize> {
+    if first & 0xE0 == 0xC0 {
+        Some(2)
+    } else if first & 0xF0 == 0xE0 {
+        Some(3)
+    } else if first & 0xF8 == 0xF0 {
+        Some(4)
+    } else {
+        None
     }
 }
 
+fn byte_pending(timeout_ms: i32) -> bool {
+    let mut fd = libc::pollfd {
+        fd: std::io::stdin().as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    // SAFETY: `fd` is a single valid pollfd for stdin, live for the call.
+    unsafe { libc::poll(&mut fd, 1, timeout_ms) > 0 }
+}
+
+fn decode_utf8_bytes(bytes: &[u8]) -> char {
+    std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|s| s.chars().next())
+        .unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-#[repr(u8)]
 pub enum Key {
-    CtrlK = 0x0B,
-    CtrlU = 0x15,
-    CtrlC = 0x03,
-    CtrlD = 0x04,
-    Backspace = 0x7F,
-    Tab = b'\t',
-    Return = b'\r',
+    CtrlK,
+    CtrlU,
+    CtrlC,
+    CtrlD,
+    CtrlW,
+    Backspace,
+    Tab,
+    Return,
+    Escape,
     Up,
     Down,
     Left,
     Right,
-    Other(u8),
+    CtrlLeft,
+    CtrlRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    F(u8),
+    Char(char),
     // Not an ideal way of representing things, but should get the job done.
     OtherEsc(u8),
     OtherCsi(u8),
 }
+
+#[derive(Default)]
+struct TrieNode {
+    key: Option<Key>,
+    children: HashMap<u8, TrieNode>,
+}
+
+impl TrieNode {
+    fn insert(&mut self, seq: &[u8], key: Key) {
+        match seq.split_first() {
+            None => self.key = Some(key),
+            Some((&b, rest)) => self.children.entry(b).or_default().insert(rest, key),
+        }
+    }
+}
+
+// Standard xterm/vt100 escape sequences (the bytes following the leading
+// 0x1B). A fuller build would seed this from terminfo capabilities like
+// kcuu1/khome/kend/kpp/knp/kich1/kdch1 instead of hardcoding them.
+fn escape_trie() -> &'static TrieNode {
+    static TRIE: OnceLock<TrieNode> = OnceLock::new();
+    TRIE.get_or_init(|| {
+        let mut root = TrieNode::default();
+        let mut seq = |s: &[u8], key: Key| root.insert(s, key);
+
+        seq(b"[A", Key::Up);
+        seq(b"[B", Key::Down);
+        seq(b"[C", Key::Right);
+        seq(b"[D", Key::Left);
+        seq(b"[1;5C", Key::CtrlRight);
+        seq(b"[1;5D", Key::CtrlLeft);
+        seq(b"[H", Key::Home);
+        seq(b"[F", Key::End);
+        seq(b"[1~", Key::Home);
+        seq(b"[2~", Key::Insert);
+        seq(b"[3~", Key::Delete);
+        seq(b"[4~", Key::End);
+        seq(b"[5~", Key::PageUp);
+        seq(b"[6~", Key::PageDown);
+        seq(b"OH", Key::Home);
+        seq(b"OF", Key::End);
+        seq(b"OP", Key::F(1));
+        seq(b"OQ", Key::F(2));
+        seq(b"OR", Key::F(3));
+        seq(b"OS", Key::F(4));
+        seq(b"[15~", Key::F(5));
+        seq(b"[17~", Key::F(6));
+        seq(b"[18~", Key::F(7));
+        seq(b"[19~", Key::F(8));
+        seq(b"[20~", Key::F(9));
+        seq(b"[21~", Key::F(10));
+        seq(b"[23~", Key::F(11));
+        seq(b"[24~", Key::F(12));
+
+        root
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_ascii() {
+        assert_eq!(decode_utf8_bytes(b"a"), 'a');
+    }
+
+    #[test]
+    fn decodes_multibyte() {
+        assert_eq!(decode_utf8_bytes("é".as_bytes()), 'é');
+        assert_eq!(decode_utf8_bytes("✓".as_bytes()), '✓');
+        assert_eq!(decode_utf8_bytes("𝄞".as_bytes()), '𝄞');
+    }
+
+    #[test]
+    fn falls_back_on_garbage() {
+        assert_eq!(decode_utf8_bytes(&[0xFF, 0xFE]), char::REPLACEMENT_CHARACTER);
+    }
+
+    #[test]
+    fn trie_resolves_known_sequences() {
+        assert_eq!(walk_trie(b"[A"), Some(Key::Up));
+        assert_eq!(walk_trie(b"[1;5C"), Some(Key::CtrlRight));
+        assert_eq!(walk_trie(b"OP"), Some(Key::F(1)));
+    }
+
+    #[test]
+    fn trie_has_no_match_for_unknown_sequence() {
+        assert_eq!(walk_trie(b"[9"), None);
+    }
+
+    fn walk_trie(seq: &[u8]) -> Option<Key> {
+        let mut node = escape_trie();
+        for &b in seq {
+            node = node.children.get(&b)?;
+        }
+        node.key
+    }
+
+    #[test]
+    fn seq_len_matches_lead_byte() {
+        assert_eq!(utf8_seq_len(0x41), None); // ascii isn't a lead byte
+        assert_eq!(utf8_seq_len(0xC2), Some(2));
+        assert_eq!(utf8_seq_len(0xE2), Some(3));
+        assert_eq!(utf8_seq_len(0xF0), Some(4));
+    }
+}
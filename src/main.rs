@@ -19,9 +19,11 @@ const FB_ACTIVATE_FORCE: u32 = 128;
 
 mod buffer;
 mod color;
+mod config;
 mod draw;
 mod greetd;
 mod input;
+mod state;
 
 #[derive(PartialEq, Copy, Clone)]
 enum Mode {
@@ -42,6 +44,7 @@ enum Error {
 }
 
 struct Target {
+    id: String,
     name: String,
     exec: Vec<String>,
 }
@@ -55,9 +58,10 @@ impl Target {
         let cmdline = entry.exec()?;
         let exec = shell_words::split(cmdline).ok()?;
 
+        let id = entry.appid.to_string();
         let name = entry.name(None).unwrap_or(entry.appid.into()).into_owned();
 
-        Some(Self { name, exec })
+        Some(Self { id, name, exec })
     }
 }
 
@@ -68,6 +72,12 @@ struct LoginManager<'a> {
     headline_font: draw::Font,
     prompt_font: draw::Font,
 
+    bg_color: Color,
+    box_color: Color,
+    text_color: Color,
+    highlight_color: Color,
+    error_color: Color,
+
     screen_size: (u32, u32),
     dimensions: (u32, u32),
     mode: Mode,
@@ -75,6 +85,7 @@ struct LoginManager<'a> {
     targets: Vec<Target>,
     target_index: usize,
     cursor_pos: usize,
+    initial_username: Option<String>,
 
     var_screen_info: &'a VarScreeninfo,
     should_refresh: bool,
@@ -84,22 +95,49 @@ impl<'a> LoginManager<'a> {
     fn new(
         fb: &'a mut Framebuffer,
         screen_size: (u32, u32),
-        dimensions: (u32, u32),
+        theme: config::Theme,
         greetd: greetd::GreetD,
         targets: Vec<Target>,
     ) -> Self {
+        let saved = state::State::load();
+
+        let target_index = saved
+            .as_ref()
+            .and_then(|saved| targets.iter().position(|t| t.id == saved.session_id))
+            .unwrap_or(1); // today's default, same as before state was persisted
+
+        let initial_username = saved.map(|saved| saved.username);
+        let mode = if initial_username.is_some() {
+            Mode::EditingPassword
+        } else {
+            Mode::EditingUsername
+        };
+
+        // Clamp to the real screen so a box larger than the display can't
+        // underflow the centering math in `offset`.
+        let dimensions = (
+            theme.box_width.min(screen_size.0),
+            theme.box_height.min(screen_size.1),
+        );
+
         Self {
             buf: &mut fb.frame,
             device: &fb.device,
-            headline_font: draw::Font::new(&draw::DEJAVUSANS_MONO, 72.0),
-            prompt_font: draw::Font::new(&draw::DEJAVUSANS_MONO, 32.0),
+            headline_font: load_font(&theme.headline_font, 72.0),
+            prompt_font: load_font(&theme.prompt_font, 32.0),
+            bg_color: theme.background,
+            box_color: theme.box_color,
+            text_color: theme.text,
+            highlight_color: theme.highlight,
+            error_color: theme.error,
             screen_size,
             dimensions,
-            mode: Mode::EditingUsername,
+            mode,
             greetd,
             targets,
-            target_index: 1, // TODO: remember last user selection
+            target_index,
             cursor_pos: 0,
+            initial_username,
             var_screen_info: &fb.var_screen_info,
             should_refresh: false,
         }
@@ -117,7 +155,7 @@ impl<'a> LoginManager<'a> {
 
     fn clear(&mut self) {
         let mut buf = buffer::Buffer::new(self.buf, self.screen_size);
-        let bg = Color::BLACK;
+        let bg = self.bg_color;
         buf.memset(&bg);
         self.should_refresh = true;
     }
@@ -132,8 +170,8 @@ impl<'a> LoginManager<'a> {
     fn draw_bg(&mut self, box_color: &Color) -> Result<(), Error> {
         let (x, y) = self.offset();
         let mut buf = buffer::Buffer::new(self.buf, self.screen_size);
-        let bg = Color::BLACK;
-        let fg = Color::WHITE;
+        let bg = self.bg_color;
+        let fg = self.text_color;
 
         draw::draw_box(
             &mut buf.subdimensions((x, y, self.dimensions.0, self.dimensions.1))?,
@@ -162,9 +200,9 @@ impl<'a> LoginManager<'a> {
         )?;
 
         let (session_color, username_color, password_color) = match self.mode {
-            Mode::SelectingSession => (Color::YELLOW, Color::WHITE, Color::WHITE),
-            Mode::EditingUsername => (Color::WHITE, Color::YELLOW, Color::WHITE),
-            Mode::EditingPassword => (Color::WHITE, Color::WHITE, Color::YELLOW),
+            Mode::SelectingSession => (self.highlight_color, fg, fg),
+            Mode::EditingUsername => (fg, self.highlight_color, fg),
+            Mode::EditingPassword => (fg, fg, self.highlight_color),
         };
 
         self.prompt_font.auto_draw_text(
@@ -203,23 +241,25 @@ impl<'a> LoginManager<'a> {
         Ok(())
     }
 
-    fn draw_target(&mut self) -> Result<(), Error> {
+    fn draw_target(&mut self, query: &str) -> Result<(), Error> {
         let (x, y) = self.offset();
         let (x, y) = (x + 416, y + 24);
         let dim = (self.dimensions.0 - 416 - 32, 32);
 
         let mut buf = buffer::Buffer::new(self.buf, self.screen_size);
         let mut buf = buf.subdimensions((x, y, dim.0, dim.1))?;
-        let bg = Color::BLACK;
+        let bg = self.bg_color;
         buf.memset(&bg);
 
-        self.prompt_font.auto_draw_text(
-            &mut buf,
-            &bg,
-            &Color::WHITE,
-            &self.targets[self.target_index].name,
-            None,
-        )?;
+        let name = &self.targets[self.target_index].name;
+        let label = if query.is_empty() {
+            name.clone()
+        } else {
+            format!("{name}  [{query}]")
+        };
+
+        self.prompt_font
+            .auto_draw_text(&mut buf, &bg, &self.text_color, &label, None)?;
 
         self.should_refresh = true;
 
@@ -233,12 +273,12 @@ impl<'a> LoginManager<'a> {
 
         let mut buf = buffer::Buffer::new(self.buf, self.screen_size);
         let mut buf = buf.subdimensions((x, y, dim.0, dim.1))?;
-        let bg = Color::BLACK;
+        let bg = self.bg_color;
         buf.memset(&bg);
 
         let cursor_pos = (self.mode == Mode::EditingUsername).then_some(self.cursor_pos);
         self.prompt_font
-            .auto_draw_text(&mut buf, &bg, &Color::WHITE, username, cursor_pos)?;
+            .auto_draw_text(&mut buf, &bg, &self.text_color, username, cursor_pos)?;
 
         self.should_refresh = true;
 
@@ -252,18 +292,18 @@ impl<'a> LoginManager<'a> {
 
         let mut buf = buffer::Buffer::new(self.buf, self.screen_size);
         let mut buf = buf.subdimensions((x, y, dim.0, dim.1))?;
-        let bg = Color::BLACK;
+        let bg = self.bg_color;
         buf.memset(&bg);
 
         let mut stars = "".to_string();
-        for _ in 0..password.len() {
+        for _ in password.chars() {
             stars += "*";
         }
 
         let cursor_pos = (self.mode == Mode::EditingPassword).then_some(self.cursor_pos);
 
         self.prompt_font
-            .auto_draw_text(&mut buf, &bg, &Color::WHITE, &stars, cursor_pos)?;
+            .auto_draw_text(&mut buf, &bg, &self.text_color, &stars, cursor_pos)?;
 
         self.should_refresh = true;
 
@@ -287,22 +327,25 @@ impl<'a> LoginManager<'a> {
     }
 
     fn greeter_loop(&mut self) {
-        let mut username = String::with_capacity(USERNAME_CAP);
+        let mut username = self.initial_username.take().unwrap_or_default();
         let mut password = String::with_capacity(PASSWORD_CAP);
+        let mut session_query = String::new();
         let mut last_username_len = username.len();
         let mut last_password_len = password.len();
         let mut last_cursor_pos = 1; // this forces the first iteration to draw the user/pass so a cursor is drawn
         let mut last_target_index = self.target_index;
+        let mut last_query_len = session_query.len();
         let mut last_mode = self.mode;
         let mut had_failure = false;
 
         let mut input = InputStream::new();
 
-        self.draw_target().expect("unable to draw target session");
+        self.draw_target(&session_query)
+            .expect("unable to draw target session");
 
         loop {
             let max_cursor_pos = match self.mode {
-                Mode::SelectingSession => 0,
+                Mode::SelectingSession => session_query.len(),
                 Mode::EditingUsername => username.len(),
                 Mode::EditingPassword => password.len(),
             };
@@ -311,7 +354,8 @@ impl<'a> LoginManager<'a> {
             let mode_changed = last_mode != self.mode;
             if mode_changed {
                 self.cursor_pos = max_cursor_pos;
-                self.draw_bg(&Color::GRAY)
+                let box_color = self.box_color;
+                self.draw_bg(&box_color)
                     .expect("unable to draw background");
             }
 
@@ -325,12 +369,14 @@ impl<'a> LoginManager<'a> {
                 self.draw_password(&password)
                     .expect("unable to draw username prompt");
             }
-            if last_target_index != self.target_index {
-                self.draw_target().expect("unable to draw target session");
+            if last_target_index != self.target_index || session_query.len() != last_query_len {
+                self.draw_target(&session_query)
+                    .expect("unable to draw target session");
             }
 
             if had_failure {
-                self.draw_bg(&Color::GRAY)
+                let box_color = self.box_color;
+                self.draw_bg(&box_color)
                     .expect("unable to draw background");
             }
 
@@ -338,12 +384,16 @@ impl<'a> LoginManager<'a> {
             last_password_len = password.len();
             last_mode = self.mode;
             last_target_index = self.target_index;
+            last_query_len = session_query.len();
             last_cursor_pos = self.cursor_pos;
             had_failure = false;
 
             match input.next() {
                 Key::CtrlK | Key::CtrlU => match self.mode {
-                    Mode::SelectingSession => (),
+                    Mode::SelectingSession => {
+                        session_query.clear();
+                        self.retarget(&session_query);
+                    }
                     Mode::EditingUsername => username.clear(),
                     Mode::EditingPassword => password.clear(),
                 },
@@ -357,7 +407,7 @@ impl<'a> LoginManager<'a> {
                     let field = match self.mode {
                         Mode::EditingUsername => &mut username,
                         Mode::EditingPassword => &mut password,
-                        Mode::SelectingSession => continue,
+                        Mode::SelectingSession => &mut session_query,
                     };
                     if k == Key::Backspace {
                         if self.cursor_pos == 0 {
@@ -368,6 +418,22 @@ impl<'a> LoginManager<'a> {
                     if self.cursor_pos < field.len() {
                         field.remove(self.cursor_pos);
                     }
+                    if self.mode == Mode::SelectingSession {
+                        self.retarget(&session_query);
+                    }
+                }
+                Key::CtrlW => {
+                    let field = match self.mode {
+                        Mode::EditingUsername => &mut username,
+                        Mode::EditingPassword => &mut password,
+                        Mode::SelectingSession => &mut session_query,
+                    };
+                    let word_start = Self::prev_word_start(field, self.cursor_pos);
+                    field.replace_range(word_start..self.cursor_pos, "");
+                    self.cursor_pos = word_start;
+                    if self.mode == Mode::SelectingSession {
+                        self.retarget(&session_query);
+                    }
                 }
                 Key::Return => match self.mode {
                     Mode::SelectingSession => self.mode = Mode::EditingUsername,
@@ -381,8 +447,11 @@ impl<'a> LoginManager<'a> {
                             username.clear();
                             self.mode = Mode::EditingUsername;
                         } else {
-                            self.draw_bg(&Color::YELLOW)
+                            let highlight_color = self.highlight_color;
+                            self.draw_bg(&highlight_color)
                                 .expect("unable to draw background");
+                            let logged_in_as = username.clone();
+                            let session_id = self.targets[self.target_index].id.clone();
                             let res = self.greetd.login(
                                 username,
                                 password,
@@ -391,9 +460,17 @@ impl<'a> LoginManager<'a> {
                             username = String::with_capacity(USERNAME_CAP);
                             password = String::with_capacity(PASSWORD_CAP);
                             match res {
-                                Ok(_) => return,
+                                Ok(_) => {
+                                    state::State {
+                                        username: logged_in_as,
+                                        session_id,
+                                    }
+                                    .save();
+                                    return;
+                                }
                                 Err(_) => {
-                                    self.draw_bg(&Color::RED)
+                                    let error_color = self.error_color;
+                                    self.draw_bg(&error_color)
                                         .expect("unable to draw background");
                                     self.mode = Mode::EditingUsername;
                                     self.greetd.cancel();
@@ -403,42 +480,104 @@ impl<'a> LoginManager<'a> {
                         }
                     }
                 },
-                Key::Up => self.goto_prev_mode(),
-                Key::Down | Key::Tab => self.goto_next_mode(),
+                Key::Up => match self.mode {
+                    Mode::SelectingSession => self.cycle_target(&session_query, -1),
+                    _ => self.goto_prev_mode(),
+                },
+                Key::Down => match self.mode {
+                    Mode::SelectingSession => self.cycle_target(&session_query, 1),
+                    _ => self.goto_next_mode(),
+                },
+                Key::Tab => self.goto_next_mode(),
                 Key::Right => match self.mode {
-                    Mode::SelectingSession => {
-                        self.target_index = (self.target_index + 1) % self.targets.len()
-                    }
+                    Mode::SelectingSession => self.cycle_target(&session_query, 1),
                     Mode::EditingUsername => self.advance_cursor(&username),
                     Mode::EditingPassword => self.advance_cursor(&password),
                 },
                 Key::Left => match self.mode {
-                    Mode::SelectingSession => {
-                        if self.target_index == 0 {
-                            self.target_index = self.targets.len();
-                        }
-                        self.target_index -= 1;
-                    }
+                    Mode::SelectingSession => self.cycle_target(&session_query, -1),
                     Mode::EditingUsername => self.retreat_cursor(&username),
                     Mode::EditingPassword => self.retreat_cursor(&password),
                 },
-                Key::Other(k) => {
+                Key::CtrlRight => {
+                    let field = match self.mode {
+                        Mode::EditingUsername => &username,
+                        Mode::EditingPassword => &password,
+                        Mode::SelectingSession => &session_query,
+                    };
+                    self.cursor_pos = Self::next_word_start(field, self.cursor_pos);
+                }
+                Key::CtrlLeft => {
+                    let field = match self.mode {
+                        Mode::EditingUsername => &username,
+                        Mode::EditingPassword => &password,
+                        Mode::SelectingSession => &session_query,
+                    };
+                    self.cursor_pos = Self::prev_word_start(field, self.cursor_pos);
+                }
+                Key::Home => self.cursor_pos = 0,
+                Key::End => {
+                    self.cursor_pos = match self.mode {
+                        Mode::SelectingSession => session_query.len(),
+                        Mode::EditingUsername => username.len(),
+                        Mode::EditingPassword => password.len(),
+                    }
+                }
+                Key::Char(ch) => {
                     let field = match self.mode {
                         Mode::EditingUsername => &mut username,
                         Mode::EditingPassword => &mut password,
-                        Mode::SelectingSession => continue,
+                        Mode::SelectingSession => &mut session_query,
                     };
-                    // TODO: proper unicode input?
-                    let ch = k as char;
                     field.insert(self.cursor_pos, ch);
                     self.cursor_pos += ch.len_utf8();
+                    if self.mode == Mode::SelectingSession {
+                        self.retarget(&session_query);
+                    }
                 }
+                Key::PageUp | Key::PageDown | Key::Insert | Key::F(_) | Key::Escape => (), // shrug
                 Key::OtherEsc(_) | Key::OtherCsi(_) => (), // shrug
             }
             self.refresh();
         }
     }
 
+    // Indices into `self.targets`, filtered and ranked by `query` (a flexible
+    // subsequence match), falling back to the unfiltered order when empty.
+    fn filtered_targets(&self, query: &str) -> Vec<usize> {
+        if query.is_empty() {
+            return (0..self.targets.len()).collect();
+        }
+
+        let mut scored: Vec<(usize, i32)> = self
+            .targets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, target)| fuzzy_score(query, &target.name).map(|score| (i, score)))
+            .collect();
+        scored.sort_by(|a, b| b.1.cmp(&a.1));
+        scored.into_iter().map(|(i, _)| i).collect()
+    }
+
+    // Points `target_index` at the top-scoring match for `query`, leaving it
+    // unchanged when nothing matches.
+    fn retarget(&mut self, query: &str) {
+        if let Some(&best) = self.filtered_targets(query).first() {
+            self.target_index = best;
+        }
+    }
+
+    // Moves `target_index` by `delta` within the filtered set, wrapping
+    // around at either end.
+    fn cycle_target(&mut self, query: &str, delta: isize) {
+        let filtered = self.filtered_targets(query);
+        let Some(pos) = filtered.iter().position(|&i| i == self.target_index) else {
+            return;
+        };
+        let next = (pos as isize + delta).rem_euclid(filtered.len() as isize) as usize;
+        self.target_index = filtered[next];
+    }
+
     fn retreat_cursor(&mut self, field: &str) {
         let Some(prev_char) = field[..self.cursor_pos].chars().last() else {
             // the cursor is already at the start of the field
@@ -454,6 +593,154 @@ impl<'a> LoginManager<'a> {
         };
         self.cursor_pos += next_char.len_utf8();
     }
+
+    // Skips the run sharing `pos`'s char class, then any trailing whitespace.
+    fn next_word_start(field: &str, pos: usize) -> usize {
+        let mut chars = field[pos..].char_indices();
+        let Some((_, first)) = chars.next() else {
+            return field.len();
+        };
+
+        let class = CharClass::of(first);
+        let mut offset = first.len_utf8();
+        for (i, ch) in chars.by_ref() {
+            if CharClass::of(ch) != class {
+                offset = i;
+                break;
+            }
+            offset = i + ch.len_utf8();
+        }
+
+        for (i, ch) in field[pos + offset..].char_indices() {
+            if CharClass::of(ch) != CharClass::Whitespace {
+                return pos + offset + i;
+            }
+        }
+        field.len()
+    }
+
+    // Mirror of next_word_start, scanning leftward.
+    fn prev_word_start(field: &str, pos: usize) -> usize {
+        let mut rest = &field[..pos];
+
+        let Some(last) = rest.chars().last() else {
+            return 0;
+        };
+        if CharClass::of(last) == CharClass::Whitespace {
+            rest = rest.trim_end_matches(|ch| CharClass::of(ch) == CharClass::Whitespace);
+        }
+
+        let Some(last) = rest.chars().last() else {
+            return 0;
+        };
+        let class = CharClass::of(last);
+        rest.trim_end_matches(|ch| CharClass::of(ch) == class).len()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CharClass {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+impl CharClass {
+    fn of(ch: char) -> Self {
+        if ch.is_whitespace() {
+            Self::Whitespace
+        } else if ch.is_alphanumeric() || ch == '_' {
+            Self::Word
+        } else {
+            Self::Punctuation
+        }
+    }
+}
+
+#[cfg(test)]
+mod word_motion_tests {
+    use super::LoginManager;
+
+    #[test]
+    fn next_word_start_skips_gap() {
+        assert_eq!(LoginManager::next_word_start("foo bar", 0), 4);
+        assert_eq!(LoginManager::next_word_start("foo  bar", 3), 5);
+        assert_eq!(LoginManager::next_word_start("foo", 0), 3);
+    }
+
+    #[test]
+    fn prev_word_start_skips_gap() {
+        assert_eq!(LoginManager::prev_word_start("foo bar", 7), 4);
+        assert_eq!(LoginManager::prev_word_start("foo  bar", 5), 0);
+        assert_eq!(LoginManager::prev_word_start("foo", 0), 0);
+    }
+}
+
+// Case-insensitive subsequence match, scoring word-boundary and consecutive
+// hits higher and gaps lower. None if `query` isn't a subsequence.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+
+    let mut score = 0;
+    let mut query_pos = 0;
+    let mut last_match: Option<usize> = None;
+
+    for (i, &ch) in candidate.iter().enumerate() {
+        if query_pos >= query.len() {
+            break;
+        }
+        if ch != query[query_pos] {
+            continue;
+        }
+
+        let at_boundary = i == 0 || !candidate[i - 1].is_alphanumeric();
+        score += if at_boundary { 10 } else { 1 };
+        score += match last_match {
+            Some(prev) if prev + 1 == i => 5,
+            Some(prev) => -((i - prev - 1) as i32),
+            None => 0,
+        };
+
+        last_match = Some(i);
+        query_pos += 1;
+    }
+
+    (query_pos == query.len()).then_some(score)
+}
+
+#[cfg(test)]
+mod fuzzy_score_tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn non_subsequence_is_none() {
+        assert_eq!(fuzzy_score("xyz", "gnome"), None);
+    }
+
+    #[test]
+    fn is_case_insensitive() {
+        assert!(fuzzy_score("GNOME", "gnome").is_some());
+    }
+
+    #[test]
+    fn consecutive_and_boundary_matches_score_higher() {
+        let prefix = fuzzy_score("gn", "gnome").unwrap();
+        let scattered = fuzzy_score("ge", "gnome").unwrap();
+        assert!(prefix > scattered);
+    }
+}
+
+// Loads a user-supplied font face if the config points at one, falling back
+// to the bundled DejaVu Sans Mono on a missing/unreadable path. `default_size`
+// is used when the config doesn't specify one (headline/prompt differ).
+fn load_font(font_config: &config::FontConfig, default_size: f32) -> draw::Font {
+    let size = font_config.size.unwrap_or(default_size);
+    let bytes = font_config.path.as_deref().and_then(|path| fs::read(path).ok());
+    match bytes {
+        Some(bytes) => draw::Font::new(&bytes, size),
+        None => draw::Font::new(&draw::DEJAVUSANS_MONO, size),
+    }
 }
 
 fn main() {
@@ -470,7 +757,11 @@ fn main() {
 
     let greetd = greetd::GreetD::new();
 
-    let targets = ["/usr/share/wayland-sessions", "/usr/share/xsessions"]
+    let config = config::Config::load();
+
+    let targets = config
+        .sessions
+        .directories
         .iter()
         .flat_map(fs::read_dir)
         .flatten()
@@ -478,10 +769,11 @@ fn main() {
         .flat_map(|dir_entry| Target::load(dir_entry.path()))
         .collect();
 
-    let mut lm = LoginManager::new(&mut framebuffer, (w, h), (1024, 168), greetd, targets);
+    let box_color = config.theme.box_color;
+    let mut lm = LoginManager::new(&mut framebuffer, (w, h), config.theme, greetd, targets);
 
     lm.clear();
-    lm.draw_bg(&Color::GRAY).expect("unable to draw background");
+    lm.draw_bg(&box_color).expect("unable to draw background");
     lm.refresh();
 
     lm.greeter_loop();
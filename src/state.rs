@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+const STATE_PATH: &str = "/var/lib/ddlm/state.toml";
+
+// Remembers the last successful username and session so returning users
+// don't have to re-pick either. Never stores passwords.
+#[derive(Serialize, Deserialize)]
+pub struct State {
+    pub username: String,
+    pub session_id: String,
+}
+
+impl State {
+    /// Reads the saved state, if any. A missing or corrupt file is treated
+    /// the same as "no prior login" rather than as an error.
+    pub fn load() -> Option<Self> {
+        let data = std::fs::read_to_string(STATE_PATH).ok()?;
+        toml::from_str(&data).ok()
+    }
+
+    pub fn save(&self) {
+        if let Ok(data) = toml::to_string_pretty(self) {
+            let _ = std::fs::create_dir_all("/var/lib/ddlm");
+            let _ = std::fs::write(STATE_PATH, data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_toml() {
+        let state = State {
+            username: "alice".to_string(),
+            session_id: "gnome".to_string(),
+        };
+        let data = toml::to_string_pretty(&state).unwrap();
+        let parsed: State = toml::from_str(&data).unwrap();
+        assert_eq!(parsed.username, "alice");
+        assert_eq!(parsed.session_id, "gnome");
+    }
+
+    #[test]
+    fn corrupt_data_fails_to_parse() {
+        let parsed: Result<State, _> = toml::from_str("not valid toml state");
+        assert!(parsed.is_err());
+    }
+}